@@ -0,0 +1,111 @@
+//! A Norvig-style spelling corrector (https://norvig.com/spell-correct.html)
+//! built on top of the frequency list this crate already produces: the counts
+//! in `frequency_list.txt` double as the word-probability prior a corrector
+//! needs, so no separate language model has to be trained.
+
+use fnv::FnvHashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+/// Loads a `frequency_list.txt`-shaped file ("word count" per line) into a map.
+pub fn load_frequency_list<P: AsRef<Path>>(path: P) -> FnvHashMap<String, usize> {
+    let path = path.as_ref();
+    let reader = BufReader::new(
+        File::open(path).unwrap_or_else(|e| panic!("failed to open frequency list {:?}: {:?}", path, e)),
+    );
+
+    let mut freq = FnvHashMap::default();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.unwrap_or_else(|e| panic!("failed to read frequency list line {}: {:?}", i, e));
+        if line.is_empty() {
+            continue;
+        }
+
+        let sep = line
+            .rfind(' ')
+            .unwrap_or_else(|| panic!("malformed frequency list line {}: {:?}", i, line));
+        let word = line[..sep].to_string();
+        let count: usize = line[sep + 1..]
+            .parse()
+            .unwrap_or_else(|e| panic!("malformed count on frequency list line {}: {:?}", i, e));
+
+        freq.insert(word, count);
+    }
+
+    return freq;
+}
+
+/// Returns `word` itself if it's already in `freq`; otherwise the
+/// highest-frequency known word at edit distance 1, or, only if there is none,
+/// the highest-frequency known word at edit distance 2. If nothing within
+/// distance 2 is known, `word` is returned unchanged.
+pub fn correct(word: &str, freq: &FnvHashMap<String, usize>) -> String {
+    if freq.contains_key(word) {
+        return word.to_string();
+    }
+
+    let candidates1 = edits1(word);
+    if let Some(best) = best_known(candidates1.iter().map(String::as_str), freq) {
+        return best;
+    }
+
+    let candidates2: Vec<String> = candidates1.iter().flat_map(|w| edits1(w)).collect();
+    if let Some(best) = best_known(candidates2.iter().map(String::as_str), freq) {
+        return best;
+    }
+
+    return word.to_string();
+}
+
+fn best_known<'a, I: Iterator<Item = &'a str>>(
+    candidates: I,
+    freq: &FnvHashMap<String, usize>,
+) -> Option<String> {
+    candidates
+        .filter_map(|w| freq.get(w).map(|&count| (w, count)))
+        .max_by_key(|&(_, count)| count)
+        .map(|(w, _)| w.to_string())
+}
+
+/// All edit-distance-1 variants of `word`: deletions, transpositions,
+/// replacements and insertions.
+fn edits1(word: &str) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let n = chars.len();
+    let mut out = Vec::with_capacity(n * (2 + 2 * ALPHABET.len()));
+
+    for i in 0..n {
+        let mut s: String = chars[..i].iter().collect();
+        s.extend(&chars[i + 1..]);
+        out.push(s);
+    }
+
+    for i in 0..n.saturating_sub(1) {
+        let mut swapped = chars.clone();
+        swapped.swap(i, i + 1);
+        out.push(swapped.into_iter().collect());
+    }
+
+    for i in 0..n {
+        for &a in ALPHABET {
+            let mut replaced = chars.clone();
+            replaced[i] = a as char;
+            out.push(replaced.into_iter().collect());
+        }
+    }
+
+    for i in 0..=n {
+        for &a in ALPHABET {
+            let mut s: String = chars[..i].iter().collect();
+            s.push(a as char);
+            s.extend(&chars[i..]);
+            out.push(s);
+        }
+    }
+
+    return out;
+}
@@ -0,0 +1,65 @@
+//! A per-document inverted index with tf-idf weights, built from the same
+//! block/descriptor plumbing as the global frequency list. Where `main`'s
+//! default mode collapses every article into one global count per word, this
+//! keeps counts keyed by `(word, article_id)` so a later query step can rank
+//! documents by sum(tf*idf) over query terms.
+
+use fnv::FnvHashMap;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Posting {
+    pub doc_id: usize,
+    pub term_frequency: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TermEntry {
+    pub document_frequency: usize,
+    pub idf: f64,
+    pub postings: Vec<Posting>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InvertedIndex {
+    pub document_count: usize,
+    pub vocabulary: FnvHashMap<String, TermEntry>,
+}
+
+impl InvertedIndex {
+    /// Builds the index out of raw `(word, doc_id) -> term_frequency` counts
+    /// (as produced by `DumpBlock::process_per_document`), computing each
+    /// term's document frequency and idf = ln(document_count / df).
+    pub fn build(counts: FnvHashMap<(String, usize), usize>, document_count: usize) -> Self {
+        let mut by_term: FnvHashMap<String, Vec<Posting>> = FnvHashMap::default();
+
+        for ((word, doc_id), term_frequency) in counts {
+            by_term.entry(word).or_default().push(Posting { doc_id, term_frequency });
+        }
+
+        let vocabulary = by_term
+            .into_iter()
+            .map(|(word, postings)| {
+                let document_frequency = postings.len();
+                let idf = (document_count as f64 / document_frequency as f64).ln();
+                (word, TermEntry { document_frequency, idf, postings })
+            })
+            .collect();
+
+        return Self { document_count, vocabulary };
+    }
+
+    /// Serializes the index to `path` via bincode, for a later query step to
+    /// load without re-decompressing the dump.
+    pub fn save<P: AsRef<Path>>(&self, path: P) {
+        let path = path.as_ref();
+        let writer = BufWriter::new(
+            File::create(path).unwrap_or_else(|e| panic!("failed to create index file {:?}: {:?}", path, e)),
+        );
+        bincode::serialize_into(writer, self)
+            .unwrap_or_else(|e| panic!("failed to serialize inverted index to {:?}: {:?}", path, e));
+    }
+}
@@ -1,37 +1,165 @@
 #![feature(pattern)]
 
+mod corrector;
+mod index;
+mod page;
+mod stem;
+mod wikitext;
+
 #[global_allocator]
 static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
 use indicatif::ParallelProgressIterator;
+use bzip2::bufread::MultiBzDecoder;
 use bzip2::read::BzDecoder;
 use clap::{App, Arg};
+use clap::ArgMatches;
+use corrector::{correct, load_frequency_list};
+use index::InvertedIndex;
+use page::{Page, PageRecord, Revision};
+use quick_xml::events::Event;
+use quick_xml::Reader;
 use rayon::prelude::*;
 use regex::{Regex, RegexBuilder};
 use fnv::{FnvHashMap, FnvHashSet};
 use std::fs::File;
 use std::io::{prelude::*, BufReader, BufWriter, SeekFrom};
 use std::path::Path;
+use stem::porter_stem;
+use wikitext::wikitext_words;
 
 fn main() {
     let args = App::new("English Wiki Frequency List Generator")
         .version("0.1.0")
         .author("Bence M. <bence.me@gmail.com>")
         .about("Generates a frequency list from an English Wikipedia dump")
+        .subcommand(App::new("correct")
+            .about("Spell-correct a word using a frequency list as the language model")
+            .arg(Arg::with_name("WORD")
+                .about("Word to correct; omit to read one word per line from stdin")
+                .index(1))
+            .arg(Arg::with_name("FREQUENCY_LIST")
+                .about("Path to the frequency list to use as the language model")
+                .short('f')
+                .long("list")
+                .default_value("frequency_list.txt")))
         .arg(Arg::with_name("DUMP")
             .about("Sets the multistream xml bz2 dump file to use")
             .short('d')
-            .long("dump"))
+            .long("dump")
+            .global(true))
         .arg(Arg::with_name("INDEX")
             .about("Sets the multistream dump file index to use (defaults to xxx-multistream-index.txt.bz2)")
             .short('i')
-            .long("index"))
+            .long("index")
+            .global(true))
         .arg(Arg::with_name("WIKTIONARY_INDEX")
             .about("Sets the wiktionary index file to use")
             .short('w')
-            .long("windex"))
+            .long("windex")
+            .global(true))
+        .arg(Arg::with_name("RAW")
+            .about("Count words straight out of the raw <text> contents instead of cleaning wikitext markup first (legacy behavior)")
+            .long("raw")
+            .global(true))
+        .arg(Arg::with_name("NO_INDEX")
+            .about("Ignore the multistream index and stream the whole dump as one continuous bz2 multi-stream instead (works on plain, non-multistream dumps, or when the index is missing/mismatched)")
+            .long("no-index")
+            .global(true))
+        .arg(Arg::with_name("STEM")
+            .about("Bucket counts by Porter stem (e.g. running/runs -> run; irregular forms like ran stay separate) instead of by surface word form")
+            .long("stem")
+            .global(true))
+        .subcommand(App::new("index")
+            .about("Build a per-document inverted index (vocabulary + tf-idf postings) instead of a single global frequency list")
+            .arg(Arg::with_name("OUTPUT")
+                .about("Path to write the serialized index to")
+                .short('o')
+                .long("output")
+                .default_value("index.bin")))
+        .subcommand(App::new("extract")
+            .about("Stream parsed Page/Revision records instead of collapsing everything to word counts")
+            .arg(Arg::with_name("OUTPUT")
+                .about("Path to write the newline-delimited JSON records to")
+                .short('o')
+                .long("output")
+                .default_value("pages.jsonl"))
+            .arg(Arg::with_name("ALL_NAMESPACES")
+                .about("Keep every namespace (default: only the main/article namespace, ns=0)")
+                .long("all-namespaces")))
         .get_matches();
 
+    if let Some(sub_args) = args.subcommand_matches("correct") {
+        return run_correct(sub_args);
+    }
+
+    if let Some(sub_args) = args.subcommand_matches("index") {
+        return run_build_index(&args, sub_args);
+    }
+
+    if let Some(sub_args) = args.subcommand_matches("extract") {
+        return run_extract_pages(&args, sub_args);
+    }
+
+    let (dump_path, index_path) = resolve_dump_paths(&args);
+    let windex_path = resolve_windex_path(&args);
+
+    println!(
+        "Files being used:\n\t{}\n\t{}\n\t{}",
+        dump_path,
+        index_path.as_deref().unwrap_or("(none, --no-index)"),
+        windex_path
+    );
+
+    let start = std::time::Instant::now();
+    let wordset = wiktionary_index_to_wordset(windex_path);
+    println!("Read wiktionary index in {:?}, found {} items", start.elapsed(), wordset.len());
+    println!("A few words from the wordlist: {:?}", wordset.iter().filter(|w| w.len() <= 5).take(10).collect::<Vec<_>>());
+
+    let raw = args.is_present("RAW");
+    let stem = args.is_present("STEM");
+
+    let start = std::time::Instant::now();
+    let mut counts: Vec<_> = if let Some(index_path) = index_path {
+        ArticleBlockIter::new(dump_path, index_path)
+            // .take(100)
+            .par_bridge()
+            // .panic_fuse()
+            .map(|mut block| block.process(&wordset, raw, stem))
+            .progress()
+            .reduce(FnvHashMap::default, |mut acc, e| {
+                for (k, v) in e {
+                    *acc.entry(k).or_insert(0) += v;
+                }
+                acc
+            })
+            .into_iter()
+            .collect()
+    } else {
+        process_whole_dump(dump_path, &wordset, raw, stem)
+            .into_iter()
+            .collect()
+    };
+    
+    println!("Counting words took {:?}", start.elapsed());
+
+    let start = std::time::Instant::now();
+    counts.sort_unstable_by_key(|&(_, v)| -(v as isize));
+    let mut writer =
+        BufWriter::new(File::create("frequency_list.txt").expect("failed to open output file"));
+    for (i, (k, v)) in counts.into_iter().enumerate() {
+        writeln!(writer, "{} {}", k, v).unwrap_or_else(|e| panic!("failed to write line {}: {:?}", i, e));
+    }
+
+    println!("Sorting and saving took {:?}", start.elapsed());
+    println!("All done");
+}
+
+/// Resolves the DUMP/INDEX args (shared by the default frequency-list mode
+/// and the `index`/`extract` subcommands, since `--no-index` etc. are
+/// declared `global(true)`) to concrete paths, falling back to the heuristic
+/// `find_file` search used by the original CLI.
+fn resolve_dump_paths(args: &ArgMatches) -> (String, Option<String>) {
     let dump_path = args
         .value_of("DUMP")
         .map(str::to_string)
@@ -43,19 +171,28 @@ fn main() {
         })
         .expect("no dump file specified and no file found from heuristic search");
 
-    let index_path = args
-        .value_of("INDEX")
-        .map(str::to_string)
-        .unwrap_or_else(|| {
+    let index_path = if args.is_present("NO_INDEX") {
+        None
+    } else {
+        Some(args.value_of("INDEX").map(str::to_string).unwrap_or_else(|| {
             const EXT: &str = ".xml.bz2";
             if dump_path.ends_with(EXT) {
                 dump_path[..dump_path.len() - EXT.len()].to_string() + "-index.txt.bz2"
             } else {
                 panic!("Can't determine index file path automatically")
             }
-        });
+        }))
+    };
 
-    let windex_path = args
+    return (dump_path, index_path);
+}
+
+/// Resolves the WIKTIONARY_INDEX arg, falling back to the heuristic
+/// `find_file` search. Only needed by modes that validate words against the
+/// wiktionary wordset (the default frequency-list mode and `index`) — not by
+/// `extract`, which doesn't tokenize at all.
+fn resolve_windex_path(args: &ArgMatches) -> String {
+    return args
         .value_of("WIKTIONARY_INDEX")
         .map(str::to_string)
         .or_else(|| {
@@ -65,45 +202,101 @@ fn main() {
             )
         })
         .expect("no wiktionary index file specified and no file found from heuristic search");
+}
 
-    println!(
-        "Files being used:\n\t{}\n\t{}\n\t{}",
-        dump_path, index_path, windex_path
+fn run_correct(sub_args: &ArgMatches) {
+    let list_path = sub_args
+        .value_of("FREQUENCY_LIST")
+        .expect("FREQUENCY_LIST has a default_value");
+
+    let freq = load_frequency_list(list_path);
+
+    if let Some(word) = sub_args.value_of("WORD") {
+        println!("{}", correct(word, &freq));
+        return;
+    }
+
+    let stdin = std::io::stdin();
+    for line in stdin.lock().lines() {
+        let line = line.expect("failed to read line from stdin");
+        let word = line.trim();
+        if word.is_empty() {
+            continue;
+        }
+
+        println!("{}", correct(word, &freq));
+    }
+}
+
+fn run_build_index(args: &ArgMatches, sub_args: &ArgMatches) {
+    let (dump_path, index_path) = resolve_dump_paths(args);
+    let index_path = index_path.expect(
+        "the `index` subcommand needs per-document ids from the multistream index; --no-index isn't supported here",
     );
 
-    let start = std::time::Instant::now();
-    let wordset = wiktionary_index_to_wordset(windex_path);
-    println!("Read wiktionary index in {:?}, found {} items", start.elapsed(), wordset.len());
-    println!("A few words from the wordlist: {:?}", wordset.iter().filter(|w| w.len() <= 5).take(10).collect::<Vec<_>>());
+    let wordset = wiktionary_index_to_wordset(resolve_windex_path(args));
+    let raw = args.is_present("RAW");
+    let stem = args.is_present("STEM");
 
-    let start = std::time::Instant::now();
-    let mut counts: Vec<_> = ArticleBlockIter::new(dump_path, index_path)
-        // .take(100)
+    let (document_count, counts) = ArticleBlockIter::new(dump_path, index_path)
         .par_bridge()
-        // .panic_fuse()
-        .map(|mut block| block.process(&wordset))
+        .map(|mut block| {
+            let document_count = block.descriptors.len();
+            (document_count, block.process_per_document(&wordset, raw, stem))
+        })
         .progress()
-        .reduce(FnvHashMap::default, |mut acc, e| {
-            for (k, v) in e {
-                *acc.entry(k).or_insert(0) += v;
+        .reduce(
+            || (0usize, FnvHashMap::default()),
+            |mut acc, (document_count, counts)| {
+                acc.0 += document_count;
+                for (k, v) in counts {
+                    *acc.1.entry(k).or_insert(0) += v;
+                }
+                acc
+            },
+        );
+
+    let index = InvertedIndex::build(counts, document_count);
+    println!(
+        "Built inverted index over {} documents, {} terms",
+        index.document_count,
+        index.vocabulary.len()
+    );
+
+    let output_path = sub_args.value_of("OUTPUT").expect("OUTPUT has a default_value");
+    index.save(output_path);
+    println!("Saved index to {}", output_path);
+}
+
+fn run_extract_pages(args: &ArgMatches, sub_args: &ArgMatches) {
+    let (dump_path, index_path) = resolve_dump_paths(args);
+    let index_path = index_path.expect(
+        "the `extract` subcommand needs the multistream index to iterate blocks; --no-index isn't supported here",
+    );
+
+    let all_namespaces = sub_args.is_present("ALL_NAMESPACES");
+    let output_path = sub_args.value_of("OUTPUT").expect("OUTPUT has a default_value");
+
+    let mut writer = BufWriter::new(
+        File::create(output_path).unwrap_or_else(|e| panic!("failed to create {:?}: {:?}", output_path, e)),
+    );
+
+    let mut written = 0usize;
+    for mut block in ArticleBlockIter::new(dump_path, index_path) {
+        for (page, revision) in block.extract_pages() {
+            if !all_namespaces && page.namespace != 0 {
+                continue;
             }
-            acc
-        })
-        .into_iter()
-        .collect();
-    
-    println!("Counting words took {:?}", start.elapsed());
 
-    let start = std::time::Instant::now();
-    counts.sort_unstable_by_key(|&(_, v)| -(v as isize));
-    let mut writer =
-        BufWriter::new(File::create("frequency_list.txt").expect("failed to open output file"));
-    for (i, (k, v)) in counts.into_iter().enumerate() {
-        writeln!(writer, "{} {}", k, v).unwrap_or_else(|e| panic!("failed to write line {}: {:?}", i, e));
+            let record = PageRecord { page, revision };
+            serde_json::to_writer(&mut writer, &record)
+                .unwrap_or_else(|e| panic!("failed to write record for {:?}: {:?}", record, e));
+            writer.write_all(b"\n").expect("failed to write newline");
+            written += 1;
+        }
     }
 
-    println!("Sorting and saving took {:?}", start.elapsed());
-    println!("All done");
+    println!("Wrote {} page records to {}", written, output_path);
 }
 
 // maybe finish this, so that the XML files have a proper header/footer
@@ -268,18 +461,13 @@ impl Iterator for ArticleBlockIter {
             .seek(SeekFrom::Start(descriptors[0].offset))
             .expect("dump file seek failed");
 
-        let mut bz_reader = BzDecoder::new(self.dump.try_clone().expect("dump file clone failed"));
-        let mut raw_xml = String::new();
-
-        raw_xml.push_str(r"<dummyroot>");
-        bz_reader
-            .read_to_string(&mut raw_xml)
-            .expect("dump file bzip decode failed");
-        raw_xml.push_str(r"</dummyroot>");
+        let bz_reader = BzDecoder::new(self.dump.try_clone().expect("dump file clone failed"));
+        let mut xml_reader = Reader::from_reader(BufReader::new(bz_reader));
+        xml_reader.trim_text(true);
 
         let block = DumpBlock {
             descriptors,
-            raw_xml,
+            xml_reader,
         };
 
         // println!("Built block {:?}", block);
@@ -290,32 +478,24 @@ impl Iterator for ArticleBlockIter {
 
 struct DumpBlock {
     descriptors: Vec<ArticleDescriptor>,
-    raw_xml: String,
+    xml_reader: Reader<BufReader<BzDecoder<File>>>,
 }
 
 impl std::fmt::Debug for DumpBlock {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "DumpBlock {{ {}-{} ({}-{}), {} descriptors, {} bytes in xml }}",
+            "DumpBlock {{ {}-{} ({}-{}), {} descriptors }}",
             self.descriptors[0].title,
             self.descriptors[self.descriptors.len()-1].title,
             self.descriptors[0].id,
             self.descriptors[self.descriptors.len()-1].id,
-            self.descriptors.len(),
-            self.raw_xml.len())
+            self.descriptors.len())
     }
 }
 
 impl DumpBlock {
-    pub fn process(&mut self, wordset: &FnvHashSet<String>) -> FnvHashMap<String, usize> {
-        thread_local! {
-            static WORD_REGEX: Regex = RegexBuilder::new(r"\w(?:(?:\.|\-|')?\w+)*")
-                .case_insensitive(true)
-                .build()
-                .expect("WORD_REGEX build failed");
-        }
-
+    pub fn process(&mut self, wordset: &FnvHashSet<String>, raw: bool, stem: bool) -> FnvHashMap<String, usize> {
         // println!(
         //     "Reading ID range {}-{} ({} entries)",
         //     self.descriptors[0].id,
@@ -324,30 +504,7 @@ impl DumpBlock {
         // );
 
         let mut counts = FnvHashMap::default();
-
-        WORD_REGEX.with(|re| {
-            let doc = roxmltree::Document::parse(&*self.raw_xml)
-                .unwrap_or_else(|e| panic!("failed to parse xml in {:?}: {:?}\n{:#?}", self, e, &self.raw_xml[..1000]));
-
-            for text_node in doc.descendants().filter(|n| n.has_tag_name("text")) {
-                let text = text_node.text();
-                if text.is_none() { continue; }
-
-                for mat in re.find_iter(text.unwrap()) {
-                    let word: String = mat.as_str()
-                        .chars()
-                        .filter(char::is_ascii_alphabetic)
-                        .map(|c| c.to_ascii_lowercase())
-                        .collect();
-                    
-                    if word.is_empty() { continue; }
-                    
-                    if wordset.contains(&word) {
-                        *counts.entry(word).or_insert(0) += 1;
-                    }
-                }
-            }
-        });
+        count_words_from_xml(&mut self.xml_reader, wordset, raw, stem, &mut counts);
 
         // println!(
         //     "Counted {} words in {} entries",
@@ -357,6 +514,294 @@ impl DumpBlock {
 
         return counts;
     }
+
+    /// Like `process`, but keys counts by `(word, article_id)` instead of folding
+    /// everything into a single global map, using the `ArticleDescriptor`s this
+    /// block was built from to assign each `<page>` its id in the order they
+    /// close. Feeds `InvertedIndex::build` for the per-document inverted index.
+    pub fn process_per_document(
+        &mut self,
+        wordset: &FnvHashSet<String>,
+        raw: bool,
+        stem: bool,
+    ) -> FnvHashMap<(String, usize), usize> {
+        let mut counts = FnvHashMap::default();
+        let mut buf = Vec::new();
+        let mut in_page = false;
+        let mut in_revision = false;
+        let mut in_text = false;
+        let mut doc_idx = 0;
+
+        loop {
+            match self.xml_reader.read_event(&mut buf) {
+                Ok(Event::Start(ref e)) => match e.name() {
+                    b"page" => in_page = true,
+                    b"revision" => in_revision = in_page,
+                    b"text" => in_text = in_page && in_revision,
+                    _ => {}
+                },
+                Ok(Event::End(ref e)) => match e.name() {
+                    b"page" => {
+                        in_page = false;
+                        doc_idx += 1;
+                    }
+                    b"revision" => in_revision = false,
+                    b"text" => in_text = false,
+                    _ => {}
+                },
+                Ok(Event::Text(e)) | Ok(Event::CData(e)) if in_text => {
+                    let text = e
+                        .unescape_and_decode(&self.xml_reader)
+                        .unwrap_or_else(|err| panic!("failed to decode text node: {:?}", err));
+                    let doc_id = self.descriptors
+                        .get(doc_idx)
+                        .unwrap_or_else(|| panic!("{:?}: saw more <page>s than descriptors", self))
+                        .id;
+
+                    for word in tokenize_and_validate(&text, raw, stem, wordset) {
+                        *counts.entry((word, doc_id)).or_insert(0) += 1;
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Ok(_) => {}
+                Err(e) => panic!("xml parse error in {:?}: {:?}", self, e),
+            }
+            buf.clear();
+        }
+
+        return counts;
+    }
+
+    /// Alternative to `process`/`process_per_document`: instead of tokenizing
+    /// into word counts, parses each `<page>`/`<revision>` into a structured
+    /// `Page`/`Revision` pair and returns them all. `ArticleDescriptor` already
+    /// carries `id`/`title`, but those don't include namespace or redirect
+    /// target, so this re-derives them from the block's own `<ns>`/`<redirect>`
+    /// elements instead.
+    pub fn extract_pages(&mut self) -> Vec<(Page, Revision)> {
+        let mut pages = Vec::new();
+        let mut buf = Vec::new();
+
+        let mut in_page = false;
+        let mut in_revision = false;
+        let mut in_title = false;
+        let mut in_ns = false;
+        let mut in_id = false;
+        let mut in_text = false;
+        let mut in_timestamp = false;
+        let mut seen_page_id = false;
+
+        let mut id = None;
+        let mut title = None;
+        let mut namespace = None;
+        let mut redirect = None;
+        let mut text = String::new();
+        let mut timestamp = String::new();
+
+        loop {
+            match self.xml_reader.read_event(&mut buf) {
+                Ok(Event::Start(ref e)) => match e.name() {
+                    b"page" => {
+                        in_page = true;
+                        id = None;
+                        title = None;
+                        namespace = None;
+                        redirect = None;
+                        text.clear();
+                        timestamp.clear();
+                        seen_page_id = false;
+                    }
+                    b"revision" => in_revision = in_page,
+                    b"title" if in_page && !in_revision => in_title = true,
+                    b"ns" if in_page && !in_revision => in_ns = true,
+                    b"id" if in_page && !in_revision && !seen_page_id => in_id = true,
+                    b"text" if in_page && in_revision => in_text = true,
+                    b"timestamp" if in_page && in_revision => in_timestamp = true,
+                    _ => {}
+                },
+                Ok(Event::Empty(ref e)) if in_page && e.name() == b"redirect" => {
+                    redirect = e
+                        .attributes()
+                        .filter_map(Result::ok)
+                        .find(|a| a.key == b"title")
+                        .map(|a| {
+                            a.unescape_and_decode_value(&self.xml_reader)
+                                .unwrap_or_else(|err| panic!("failed to decode redirect title: {:?}", err))
+                        });
+                }
+                Ok(Event::Text(e)) | Ok(Event::CData(e)) => {
+                    if in_title {
+                        title = Some(e.unescape_and_decode(&self.xml_reader).unwrap_or_else(|err| {
+                            panic!("failed to decode title: {:?}", err)
+                        }));
+                    } else if in_ns {
+                        let s = e.unescape_and_decode(&self.xml_reader)
+                            .unwrap_or_else(|err| panic!("failed to decode ns: {:?}", err));
+                        namespace = Some(s.parse().unwrap_or_else(|err| panic!("malformed ns {:?}: {:?}", s, err)));
+                    } else if in_id {
+                        let s = e.unescape_and_decode(&self.xml_reader)
+                            .unwrap_or_else(|err| panic!("failed to decode id: {:?}", err));
+                        id = Some(s.parse().unwrap_or_else(|err| panic!("malformed id {:?}: {:?}", s, err)));
+                    } else if in_text {
+                        text.push_str(&e.unescape_and_decode(&self.xml_reader).unwrap_or_else(|err| {
+                            panic!("failed to decode text: {:?}", err)
+                        }));
+                    } else if in_timestamp {
+                        timestamp = e.unescape_and_decode(&self.xml_reader).unwrap_or_else(|err| {
+                            panic!("failed to decode timestamp: {:?}", err)
+                        });
+                    }
+                }
+                Ok(Event::End(ref e)) => match e.name() {
+                    b"title" => in_title = false,
+                    b"ns" => in_ns = false,
+                    b"id" if in_id => {
+                        in_id = false;
+                        seen_page_id = true;
+                    }
+                    b"text" => in_text = false,
+                    b"timestamp" => in_timestamp = false,
+                    b"revision" => in_revision = false,
+                    b"page" => {
+                        in_page = false;
+                        pages.push((
+                            Page {
+                                id: id.unwrap_or_else(|| panic!("{:?}: <page> missing <id>", self)),
+                                title: title.clone().unwrap_or_else(|| panic!("{:?}: <page> missing <title>", self)),
+                                namespace: namespace.unwrap_or(0),
+                                redirect: redirect.clone(),
+                            },
+                            Revision {
+                                text: std::mem::take(&mut text),
+                                timestamp: timestamp.clone(),
+                            },
+                        ));
+                    }
+                    _ => {}
+                },
+                Ok(Event::Eof) => break,
+                Ok(_) => {}
+                Err(e) => panic!("xml parse error in {:?}: {:?}", self, e),
+            }
+            buf.clear();
+        }
+
+        return pages;
+    }
+}
+
+/// Tokenizes a single `<text>` node's contents and keeps only the words that are
+/// in `wordset` (optionally reduced to their Porter stem), lowercased the same
+/// way regardless of which tokenizer produced them.
+fn tokenize_and_validate(text: &str, raw: bool, stem: bool, wordset: &FnvHashSet<String>) -> Vec<String> {
+    thread_local! {
+        static WORD_REGEX: Regex = RegexBuilder::new(r"\w(?:(?:\.|\-|')?\w+)*")
+            .case_insensitive(true)
+            .build()
+            .expect("WORD_REGEX build failed");
+    }
+
+    let mut words = Vec::new();
+
+    if raw {
+        WORD_REGEX.with(|re| {
+            for mat in re.find_iter(text) {
+                let word: String = mat.as_str()
+                    .chars()
+                    .filter(char::is_ascii_alphabetic)
+                    .map(|c| c.to_ascii_lowercase())
+                    .collect();
+
+                if word.is_empty() { continue; }
+
+                if wordset.contains(&word) {
+                    words.push(if stem { porter_stem(&word) } else { word });
+                }
+            }
+        });
+    } else {
+        // wikitext_words already strips templates/links/URLs/HTML and cuts off
+        // at the "See Also"/"External Links" sections, so what comes back is
+        // article prose rather than raw markup.
+        for word in wikitext_words(text) {
+            if wordset.contains(&word) {
+                words.push(if stem { porter_stem(&word) } else { word });
+            }
+        }
+    }
+
+    return words;
+}
+
+/// Streams `<page>`/`<revision>`/`<text>` events out of `xml_reader` and folds every
+/// counted word into `counts`, without ever materializing the whole document into a
+/// string or a tree. Shared by the per-block (`ArticleBlockIter`) and whole-dump
+/// (`--no-index`) reading paths, since both just hand this a reader that happens to
+/// end (`Event::Eof`) at a different point.
+fn count_words_from_xml<R: BufRead>(
+    xml_reader: &mut Reader<R>,
+    wordset: &FnvHashSet<String>,
+    raw: bool,
+    stem: bool,
+    counts: &mut FnvHashMap<String, usize>,
+) {
+    let mut buf = Vec::new();
+    let mut in_page = false;
+    let mut in_revision = false;
+    let mut in_text = false;
+
+    loop {
+        match xml_reader.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) => match e.name() {
+                b"page" => in_page = true,
+                b"revision" => in_revision = in_page,
+                b"text" => in_text = in_page && in_revision,
+                _ => {}
+            },
+            Ok(Event::End(ref e)) => match e.name() {
+                b"page" => in_page = false,
+                b"revision" => in_revision = false,
+                b"text" => in_text = false,
+                _ => {}
+            },
+            Ok(Event::Text(e)) | Ok(Event::CData(e)) if in_text => {
+                let text = e
+                    .unescape_and_decode(&*xml_reader)
+                    .unwrap_or_else(|err| panic!("failed to decode text node: {:?}", err));
+
+                for word in tokenize_and_validate(&text, raw, stem, wordset) {
+                    *counts.entry(word).or_insert(0) += 1;
+                }
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => panic!("xml parse error: {:?}", e),
+        }
+        buf.clear();
+    }
+}
+
+/// Streams the whole dump as one continuous bz2 multi-stream via `MultiBzDecoder`,
+/// without requiring a `-multistream-index.txt.bz2` file: every member is
+/// concatenated into a single XML "document" (really just a run of `<page>`
+/// elements) and `<page>` units are word-counted as they're parsed. This makes the
+/// tool usable on plain (non-multistream) dumps and dumps whose index is missing or
+/// mismatched, at the cost of the parallelism the index-driven `ArticleBlockIter`
+/// gets from being able to seek to per-block offsets.
+fn process_whole_dump<D: AsRef<Path>>(
+    dump_path: D,
+    wordset: &FnvHashSet<String>,
+    raw: bool,
+    stem: bool,
+) -> FnvHashMap<String, usize> {
+    let dump_file = File::open(dump_path).expect("unable to open dump file");
+    let decoder = MultiBzDecoder::new(BufReader::new(dump_file));
+    let mut xml_reader = Reader::from_reader(BufReader::new(decoder));
+    xml_reader.trim_text(true);
+
+    let mut counts = FnvHashMap::default();
+    count_words_from_xml(&mut xml_reader, wordset, raw, stem, &mut counts);
+    return counts;
 }
 
 #[derive(Debug, Clone)]
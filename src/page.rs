@@ -0,0 +1,28 @@
+//! Structured page/revision records, for consumers that want document
+//! structure preserved instead of collapsed into word counts: namespace
+//! filtering (skip Talk:/User:/Template: pages), following redirects, or
+//! re-tokenizing later without re-decompressing the dump.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page {
+    pub id: usize,
+    pub title: String,
+    pub namespace: i64,
+    pub redirect: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Revision {
+    pub text: String,
+    pub timestamp: String,
+}
+
+/// A `Page` paired with its (sole, in a multistream dump) `Revision`, the unit
+/// `extract` writes out, one newline-delimited JSON object per record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageRecord {
+    pub page: Page,
+    pub revision: Revision,
+}
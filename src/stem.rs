@@ -0,0 +1,312 @@
+//! Porter stemmer (M.F. Porter, 1980), used by `--stem` to bucket frequency
+//! counts by word stem (e.g. "running"/"runs" -> "run") instead of by surface
+//! form. Only suffixes are stripped, so irregular forms such as "ran" keep
+//! their own entry.
+//!
+//! The algorithm strips suffixes in five ordered steps, each gated on the
+//! "measure" `m` of the remaining stem: the number of vowel-group ->
+//! consonant-group transitions in the `[C](VC){m}[V]` decomposition of the
+//! stem. Input is assumed to already be lowercase ASCII, which is what
+//! `wikitext_words`/`WORD_REGEX` hand back.
+
+pub fn porter_stem(word: &str) -> String {
+    if word.len() <= 2 {
+        return word.to_string();
+    }
+
+    let mut w: Vec<u8> = word.bytes().collect();
+
+    step_1a(&mut w);
+    step_1b(&mut w);
+    step_1c(&mut w);
+    step_2(&mut w);
+    step_3(&mut w);
+    step_4(&mut w);
+    step_5(&mut w);
+
+    String::from_utf8(w).expect("porter_stem: non-ascii input")
+}
+
+fn is_vowel_at(w: &[u8], i: usize, vowels: &[bool]) -> bool {
+    match w[i] {
+        b'a' | b'e' | b'i' | b'o' | b'u' => true,
+        b'y' => i > 0 && !vowels[i - 1],
+        _ => false,
+    }
+}
+
+fn vowels(w: &[u8]) -> Vec<bool> {
+    let mut v = vec![false; w.len()];
+    for i in 0..w.len() {
+        v[i] = is_vowel_at(w, i, &v);
+    }
+    v
+}
+
+/// m = number of vowel-group -> consonant-group transitions in `w`.
+fn measure(w: &[u8]) -> usize {
+    let v = vowels(w);
+    let mut m = 0;
+    let mut in_vowel_run = false;
+    for is_v in v {
+        if is_v {
+            in_vowel_run = true;
+        } else if in_vowel_run {
+            m += 1;
+            in_vowel_run = false;
+        }
+    }
+    m
+}
+
+fn contains_vowel(w: &[u8]) -> bool {
+    vowels(w).into_iter().any(|v| v)
+}
+
+/// *d: `w` ends in a double consonant (e.g. "-tt", "-ss").
+fn ends_double_consonant(w: &[u8]) -> bool {
+    let n = w.len();
+    n >= 2 && w[n - 1] == w[n - 2] && !vowels(w)[n - 1]
+}
+
+/// *o: `w` ends consonant-vowel-consonant, where the last consonant is not
+/// w, x or y (e.g. "-wil", "-hop").
+fn ends_cvc(w: &[u8]) -> bool {
+    let n = w.len();
+    if n < 3 {
+        return false;
+    }
+    let v = vowels(w);
+    !v[n - 3] && v[n - 2] && !v[n - 1] && !matches!(w[n - 1], b'w' | b'x' | b'y')
+}
+
+fn ends_with(w: &[u8], suffix: &str) -> bool {
+    w.ends_with(suffix.as_bytes())
+}
+
+fn replace_suffix(w: &mut Vec<u8>, suffix: &str, replacement: &str) {
+    let stem_len = w.len() - suffix.len();
+    w.truncate(stem_len);
+    w.extend_from_slice(replacement.as_bytes());
+}
+
+/// Applies the first (i.e. longest-matching) rule in `rules` whose suffix `w`
+/// ends with, replacing it only if the remaining stem's measure meets `min_m`.
+/// `rules` must be sorted by descending suffix length so overlapping endings
+/// (e.g. "ational" / "tional") pick the longer one, matching the algorithm's
+/// "longest matching suffix" rule.
+fn apply_rule_step(w: &mut Vec<u8>, rules: &[(&str, &str, usize)]) {
+    for &(suffix, replacement, min_m) in rules {
+        if ends_with(w, suffix) {
+            let stem_len = w.len() - suffix.len();
+            if measure(&w[..stem_len]) >= min_m {
+                replace_suffix(w, suffix, replacement);
+            }
+            return;
+        }
+    }
+}
+
+/// Step 1a: plurals. SSES -> SS, IES -> I, SS -> SS, S -> ''.
+fn step_1a(w: &mut Vec<u8>) {
+    if ends_with(w, "sses") {
+        replace_suffix(w, "sses", "ss");
+    } else if ends_with(w, "ies") {
+        replace_suffix(w, "ies", "i");
+    } else if ends_with(w, "ss") {
+        // unchanged
+    } else if ends_with(w, "s") {
+        w.truncate(w.len() - 1);
+    }
+}
+
+/// Step 1b: EED/ED/ING, with fixups restoring a dropped "e" or undoubling a
+/// final double consonant.
+fn step_1b(w: &mut Vec<u8>) {
+    if ends_with(w, "eed") {
+        if measure(&w[..w.len() - "eed".len()]) > 0 {
+            replace_suffix(w, "eed", "ee");
+        }
+        return;
+    }
+
+    let stripped = if ends_with(w, "ed") && contains_vowel(&w[..w.len() - "ed".len()]) {
+        w.truncate(w.len() - "ed".len());
+        true
+    } else if ends_with(w, "ing") && contains_vowel(&w[..w.len() - "ing".len()]) {
+        w.truncate(w.len() - "ing".len());
+        true
+    } else {
+        false
+    };
+
+    if !stripped {
+        return;
+    }
+
+    if ends_with(w, "at") || ends_with(w, "bl") || ends_with(w, "iz") {
+        w.push(b'e');
+    } else if ends_double_consonant(w) && !matches!(w[w.len() - 1], b'l' | b's' | b'z') {
+        w.pop();
+    } else if measure(w) == 1 && ends_cvc(w) {
+        w.push(b'e');
+    }
+}
+
+/// Step 1c: (*v*) Y -> I, when a vowel precedes the terminal y.
+fn step_1c(w: &mut Vec<u8>) {
+    if ends_with(w, "y") && contains_vowel(&w[..w.len() - 1]) {
+        let last = w.len() - 1;
+        w[last] = b'i';
+    }
+}
+
+/// Step 2: derivational suffixes, gated on m > 0.
+fn step_2(w: &mut Vec<u8>) {
+    const RULES: &[(&str, &str, usize)] = &[
+        ("ational", "ate", 1),
+        ("ization", "ize", 1),
+        ("iveness", "ive", 1),
+        ("fulness", "ful", 1),
+        ("ousness", "ous", 1),
+        ("tional", "tion", 1),
+        ("biliti", "ble", 1),
+        ("entli", "ent", 1),
+        ("ousli", "ous", 1),
+        ("ation", "ate", 1),
+        ("alism", "al", 1),
+        ("aliti", "al", 1),
+        ("iviti", "ive", 1),
+        ("enci", "ence", 1),
+        ("anci", "ance", 1),
+        ("izer", "ize", 1),
+        ("abli", "able", 1),
+        ("alli", "al", 1),
+        ("ator", "ate", 1),
+        ("eli", "e", 1),
+    ];
+    apply_rule_step(w, RULES);
+}
+
+/// Step 3: more derivational suffixes, gated on m > 0.
+fn step_3(w: &mut Vec<u8>) {
+    const RULES: &[(&str, &str, usize)] = &[
+        ("icate", "ic", 1),
+        ("ative", "", 1),
+        ("alize", "al", 1),
+        ("iciti", "ic", 1),
+        ("ical", "ic", 1),
+        ("ness", "", 1),
+        ("ful", "", 1),
+    ];
+    apply_rule_step(w, RULES);
+}
+
+/// Step 4: drop remaining derivational suffixes, gated on m > 1. ION is
+/// special-cased: it only drops when the preceding stem ends in S or T.
+fn step_4(w: &mut Vec<u8>) {
+    const RULES: &[(&str, &str, usize)] = &[
+        ("ement", "", 2),
+        ("ment", "", 2),
+        ("able", "", 2),
+        ("ible", "", 2),
+        ("ance", "", 2),
+        ("ence", "", 2),
+        ("ant", "", 2),
+        ("ent", "", 2),
+        ("ism", "", 2),
+        ("ate", "", 2),
+        ("iti", "", 2),
+        ("ous", "", 2),
+        ("ive", "", 2),
+        ("ize", "", 2),
+        ("al", "", 2),
+        ("er", "", 2),
+        ("ic", "", 2),
+        ("ou", "", 2),
+    ];
+
+    if ends_with(w, "ion") {
+        let stem_len = w.len() - "ion".len();
+        let stem = &w[..stem_len];
+        if measure(stem) > 1 && matches!(stem.last(), Some(b's') | Some(b't')) {
+            w.truncate(stem_len);
+        }
+        return;
+    }
+
+    apply_rule_step(w, RULES);
+}
+
+/// Step 5: drop a final "e" (m>1, or m=1 and not *o) and undouble a final "ll"
+/// (m>1).
+fn step_5(w: &mut Vec<u8>) {
+    if ends_with(w, "e") {
+        let stem_len = w.len() - 1;
+        let m = measure(&w[..stem_len]);
+        if m > 1 || (m == 1 && !ends_cvc(&w[..stem_len])) {
+            w.truncate(stem_len);
+        }
+    }
+
+    if measure(w) > 1 && ends_with(w, "ll") {
+        w.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::porter_stem;
+
+    fn check(pairs: &[(&str, &str)]) {
+        for &(word, stem) in pairs {
+            assert_eq!(porter_stem(word), stem, "stemming {:?}", word);
+        }
+    }
+
+    #[test]
+    fn step_1a_plurals() {
+        check(&[("caresses", "caress"), ("ponies", "poni"), ("ties", "ti"), ("cats", "cat")]);
+    }
+
+    #[test]
+    fn step_1b_verb_endings() {
+        check(&[
+            ("agreed", "agre"),
+            ("plastered", "plaster"),
+            ("motoring", "motor"),
+            ("hopping", "hop"),
+            ("tanned", "tan"),
+            ("falling", "fall"),
+            ("sized", "size"),
+            ("conflated", "conflat"),
+            ("troubled", "troubl"),
+        ]);
+    }
+
+    #[test]
+    fn step_1c_terminal_y() {
+        check(&[("happy", "happi"), ("sky", "sky")]);
+    }
+
+    #[test]
+    fn step_2_derivational_suffixes() {
+        check(&[
+            ("relational", "relat"),
+            ("conditional", "condit"),
+            ("rational", "ration"),
+            ("digitizer", "digit"),
+            ("differentli", "differ"),
+        ]);
+    }
+
+    #[test]
+    fn step_4_remaining_suffixes() {
+        check(&[("revival", "reviv"), ("allowance", "allow"), ("adoption", "adopt")]);
+    }
+
+    #[test]
+    fn short_words_are_left_alone() {
+        check(&[("a", "a"), ("is", "is")]);
+    }
+}